@@ -0,0 +1,340 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::{anyhow, Result};
+use object::{Object, ObjectSection, ObjectSymbol};
+
+use super::{context::Context, rtti::ClassInfo, util::convert_pointer};
+
+// Base-class offset-flags bits in `__vmi_class_type_info::base_info`.
+const VMI_BASE_IS_VIRTUAL: u64 = 0x1;
+const VMI_BASE_IS_PUBLIC: u64 = 0x2;
+const VMI_OFFSET_SHIFT: u64 = 8;
+
+pub fn find_itanium_vtables(context: &mut Context<'_>) -> Result<BTreeMap<u64, ClassInfo>> {
+    // 1. Find vtable group candidates: runs of pointers into code sections, as emitted
+    // into the vtable's virtual function slots. The address code actually references is
+    // two words into the group (past offset-to-top and the typeinfo pointer). Dispatch
+    // off section *kind* rather than name: ELF calls this section `.data.rel.ro` (or
+    // `.rodata` when nothing needs relocating) while Mach-O calls it `__const`/`__data`,
+    // but both report the same `object::SectionKind` regardless of format.
+    let code_ranges = context
+        .object
+        .sections()
+        .filter(|s| s.kind() == object::SectionKind::Text)
+        .map(|s| (s.address(), s.address() + s.size()))
+        .collect::<Vec<_>>();
+
+    let data_sections = context
+        .object
+        .sections()
+        .filter(|s| matches!(s.kind(), object::SectionKind::ReadOnlyData | object::SectionKind::Data))
+        .collect::<Vec<_>>();
+
+    if data_sections.is_empty() {
+        return Err(anyhow!("No read-only or initialized data section to scan for vtables"));
+    }
+
+    struct State {
+        last: Option<u64>,
+        all: BTreeSet<u64>,
+    }
+
+    let mut vtable_groups = BTreeSet::new();
+    for section in &data_sections {
+        let data = section.data()?;
+        let section_addr = section.address();
+
+        let groups = data
+            .windows(context.pointer_size)
+            .enumerate()
+            .step_by(context.pointer_size)
+            .fold(
+                State {
+                    last: None,
+                    all: BTreeSet::new(),
+                },
+                |mut state, (i, x)| {
+                    let ptr = convert_pointer(x, context.pointer_size);
+                    let addr = i as u64 + section_addr;
+
+                    if code_ranges.iter().any(|&(start, end)| start < ptr && ptr < end) {
+                        if state.last.is_none() {
+                            log::trace!("vtable group candidate at {:#x}", addr);
+                            state.last = Some(addr - 2 * context.pointer_size as u64);
+                        }
+                    } else if state.last.is_some() {
+                        state.all.insert(state.last.unwrap());
+                        state.last = None;
+                    }
+
+                    state
+                },
+            )
+            .all;
+
+        vtable_groups.extend(groups);
+    }
+
+    // 2. Resolve each candidate's typeinfo pointer into a reconstructed class.
+    let mut result = BTreeMap::new();
+    for group_addr in vtable_groups {
+        let vtable = group_addr + 2 * context.pointer_size as u64;
+
+        if let Some(info) = parse_itanium_rtti(context, group_addr)? {
+            log::debug!("Found vtable {:#x}: {}", vtable, info.name);
+            result.insert(vtable, info);
+        }
+    }
+
+    Ok(result)
+}
+
+fn parse_itanium_rtti(context: &mut Context<'_>, group_addr: u64) -> Result<Option<ClassInfo>> {
+    let pointer_size = context.pointer_size as u64;
+    let typeinfo_slot_addr = group_addr + pointer_size;
+
+    let typeinfo_addr = match read_pointer_at(context, typeinfo_slot_addr) {
+        Ok(x) => x,
+        Err(_) => return Ok(None),
+    };
+
+    let kind = match type_info_kind(context, typeinfo_addr)? {
+        Some(x) => x,
+        None => return Ok(None),
+    };
+
+    let name_ptr_addr = typeinfo_addr + pointer_size;
+    let name_addr = read_pointer_at(context, name_ptr_addr)?;
+    let name = demangle_itanium_name(&read_cstr(context, name_addr)?).ok_or_else(|| anyhow!("Not a class/struct typeinfo name"))?;
+
+    let bases = match kind {
+        TypeInfoKind::Class => Vec::new(),
+        TypeInfoKind::SiClass => {
+            let base_typeinfo_addr = read_pointer_at(context, typeinfo_addr + 2 * pointer_size)?;
+            vec![typeinfo_name(context, base_typeinfo_addr)?]
+        }
+        TypeInfoKind::VmiClass => parse_vmi_bases(context, typeinfo_addr, pointer_size)?,
+    };
+
+    context.xrefs.entry(typeinfo_addr).or_insert_with(Vec::new).push(group_addr + 2 * pointer_size);
+
+    Ok(Some(ClassInfo { name, bases }))
+}
+
+enum TypeInfoKind {
+    Class,
+    SiClass,
+    VmiClass,
+}
+
+/// Identifies which `*_type_info` layout `typeinfo_addr` uses by looking at the vtable
+/// pointer at its head, which points into the external `__{,si_,vmi_}class_type_info`
+/// vtable (one pointer-size past its own RTTI slot).
+fn type_info_kind(context: &Context<'_>, typeinfo_addr: u64) -> Result<Option<TypeInfoKind>> {
+    let symbol_name = resolve_vtable_pointer_symbol(context, typeinfo_addr)?;
+
+    Ok(match symbol_name.as_deref() {
+        Some("_ZTVN10__cxxabiv117__class_type_infoE") => Some(TypeInfoKind::Class),
+        Some("_ZTVN10__cxxabiv120__si_class_type_infoE") => Some(TypeInfoKind::SiClass),
+        Some("_ZTVN10__cxxabiv121__vmi_class_type_infoE") => Some(TypeInfoKind::VmiClass),
+        _ => None,
+    })
+}
+
+/// Resolves the symbol that the type_info's leading vtable-pointer field refers to. On a
+/// PIE ELF executable or shared library that field is patched in by a dynamic relocation
+/// against the imported `_ZTVN10__cxxabiv1*` symbol, so the dynamic relocation table is
+/// consulted first, then the containing section's own relocations; only when neither has
+/// an entry for the field are its bytes read and looked up by address. Mach-O bind and
+/// chained-fixup information is not read, so on Mach-O the field is always looked up by
+/// address.
+fn resolve_vtable_pointer_symbol(context: &Context<'_>, typeinfo_addr: u64) -> Result<Option<String>> {
+    if let Some(mut relocations) = context.object.dynamic_relocations() {
+        if let Some((_, relocation)) = relocations.find(|(address, _)| *address == typeinfo_addr) {
+            if let object::RelocationTarget::Symbol(index) = relocation.target() {
+                if let Some(symbol) = context.object.dynamic_symbols().find(|s| s.index() == index) {
+                    return Ok(Some(symbol.name()?.to_owned()));
+                }
+            }
+        }
+    }
+
+    for section in context.object.sections() {
+        let start = section.address();
+        let size = section.size();
+
+        if !(start <= typeinfo_addr && typeinfo_addr < start + size) {
+            continue;
+        }
+
+        let field_offset = typeinfo_addr - start;
+        if let Some((_, relocation)) = section.relocations().find(|(offset, _)| *offset == field_offset) {
+            if let object::RelocationTarget::Symbol(index) = relocation.target() {
+                return Ok(Some(context.object.symbol_by_index(index)?.name()?.to_owned()));
+            }
+        }
+
+        break;
+    }
+
+    let vtable_ptr = read_pointer_at(context, typeinfo_addr)?;
+    Ok(context
+        .object
+        .symbols()
+        .find(|s| s.address() <= vtable_ptr && vtable_ptr < s.address() + s.size().max(1))
+        .map(|s| s.name().unwrap_or_default().to_owned()))
+}
+
+fn parse_vmi_bases(context: &Context<'_>, typeinfo_addr: u64, pointer_size: u64) -> Result<Vec<String>> {
+    // __vmi_class_type_info: {vtable, name, flags: u32, base_count: u32, base_info[]}
+    let base_count_addr = typeinfo_addr + 2 * pointer_size + 4;
+    let base_count = read_u32_at(context, base_count_addr)?;
+
+    let base_info_addr = base_count_addr + 4;
+    let base_info_stride = 2 * pointer_size;
+
+    let mut bases = Vec::new();
+    for i in 0..base_count as u64 {
+        let entry_addr = base_info_addr + i * base_info_stride;
+
+        let base_typeinfo_addr = read_pointer_at(context, entry_addr)?;
+        let offset_flags = read_pointer_at(context, entry_addr + pointer_size)?;
+
+        let is_virtual = offset_flags & VMI_BASE_IS_VIRTUAL != 0;
+        let is_public = offset_flags & VMI_BASE_IS_PUBLIC != 0;
+        let offset = (offset_flags >> VMI_OFFSET_SHIFT) as i64;
+        log::trace!(
+            "vmi base at offset {:#x} (virtual={}, public={})",
+            offset,
+            is_virtual,
+            is_public
+        );
+
+        bases.push(typeinfo_name(context, base_typeinfo_addr)?);
+    }
+
+    Ok(bases)
+}
+
+fn typeinfo_name(context: &Context<'_>, typeinfo_addr: u64) -> Result<String> {
+    let pointer_size = context.pointer_size as u64;
+    let name_addr = read_pointer_at(context, typeinfo_addr + pointer_size)?;
+
+    demangle_itanium_name(&read_cstr(context, name_addr)?).ok_or_else(|| anyhow!("Not a class/struct typeinfo name"))
+}
+
+/// Demangles a `_ZTS`-referenced type_info name, e.g. `3Foo` (`Foo`) or
+/// `N3Bar3FooE` (`Bar::Foo`). Does not attempt substitutions or templates.
+fn demangle_itanium_name(mangled: &str) -> Option<String> {
+    let inner = mangled.strip_prefix('N').and_then(|s| s.strip_suffix('E')).unwrap_or(mangled);
+
+    let mut parts = Vec::new();
+    let mut rest = inner;
+    while !rest.is_empty() {
+        let digits_len = rest.find(|c: char| !c.is_ascii_digit())?;
+        if digits_len == 0 {
+            return None;
+        }
+
+        let len: usize = rest[..digits_len].parse().ok()?;
+        let (part, remainder) = rest[digits_len..].split_at_checked(len)?;
+        parts.push(part.to_owned());
+        rest = remainder;
+    }
+
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join("::"))
+    }
+}
+
+fn read_pointer_at(context: &Context<'_>, addr: u64) -> Result<u64> {
+    for section in context.object.sections() {
+        let start = section.address();
+        let size = section.size();
+
+        if start <= addr && addr < start + size {
+            let data = section.data()?;
+            let offset = (addr - start) as usize;
+            let bytes = data
+                .get(offset..offset + context.pointer_size)
+                .ok_or_else(|| anyhow!("Read out of bounds at {:#x}", addr))?;
+
+            return Ok(convert_pointer(bytes, context.pointer_size));
+        }
+    }
+
+    Err(anyhow!("No section contains address {:#x}", addr))
+}
+
+fn read_u32_at(context: &Context<'_>, addr: u64) -> Result<u32> {
+    for section in context.object.sections() {
+        let start = section.address();
+        let size = section.size();
+
+        if start <= addr && addr < start + size {
+            let data = section.data()?;
+            let offset = (addr - start) as usize;
+            let bytes = data.get(offset..offset + 4).ok_or_else(|| anyhow!("Read out of bounds at {:#x}", addr))?;
+
+            return Ok(u32::from_le_bytes(bytes.try_into().unwrap()));
+        }
+    }
+
+    Err(anyhow!("No section contains address {:#x}", addr))
+}
+
+fn read_cstr(context: &Context<'_>, addr: u64) -> Result<String> {
+    for section in context.object.sections() {
+        let start = section.address();
+        let size = section.size();
+
+        if start <= addr && addr < start + size {
+            let data = section.data()?;
+            let offset = (addr - start) as usize;
+
+            let end = data[offset..].iter().position(|&b| b == 0).ok_or_else(|| anyhow!("Unterminated string at {:#x}", addr))?;
+
+            return Ok(std::str::from_utf8(&data[offset..offset + end])?.to_owned());
+        }
+    }
+
+    Err(anyhow!("No section contains address {:#x}", addr))
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::fs;
+
+    use super::{find_itanium_vtables, ClassInfo};
+    use crate::context::Context;
+
+    // A default (PIE) build, so the typeinfo vtable pointers are resolved via
+    // relocations rather than read directly out of the section.
+    #[tokio::test]
+    async fn test_x64_pie() -> anyhow::Result<()> {
+        let file = fs::read("./test_data/itanium_rtti1_x64.so").await?;
+        let obj = object::File::parse(&*file)?;
+        let mut context = Context::new(obj)?;
+
+        let classes = find_itanium_vtables(&mut context)?;
+
+        assert_eq!(
+            classes.get(&0x3db8).unwrap(),
+            &ClassInfo {
+                name: "A".to_owned(),
+                bases: vec![],
+            }
+        );
+        assert_eq!(
+            classes.get(&0x3e18).unwrap(),
+            &ClassInfo {
+                name: "D".to_owned(),
+                bases: vec!["B".to_owned(), "C".to_owned()],
+            }
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,207 @@
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, Result};
+use object::{Object, ObjectSection};
+
+use super::{context::Context, util::convert_pointer};
+
+/// A C++ class reconstructed from its RTTI, keyed by vtable address in [`find_rtti`]
+/// (MSVC) and in `find_itanium_vtables` (Itanium).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClassInfo {
+    pub name: String,
+    pub bases: Vec<String>,
+}
+
+// Offsets into `_RTTICompleteObjectLocator`.
+const COL_OFFSET_SIGNATURE: usize = 0;
+const COL_OFFSET_TYPE_DESCRIPTOR: usize = 12;
+const COL_OFFSET_CLASS_HIERARCHY_DESCRIPTOR: usize = 16;
+
+// Offsets into `_TypeDescriptor`, after the leading vftable/spare pointer pair.
+const TYPE_DESCRIPTOR_NAME_OFFSET_32: usize = 8;
+const TYPE_DESCRIPTOR_NAME_OFFSET_64: usize = 16;
+
+// Offsets into `_RTTIClassHierarchyDescriptor`.
+const CHD_OFFSET_NUM_BASE_CLASSES: usize = 8;
+const CHD_OFFSET_BASE_CLASS_ARRAY: usize = 12;
+
+// Offsets into `_RTTIBaseClassDescriptor`.
+const BCD_OFFSET_TYPE_DESCRIPTOR: usize = 0;
+
+pub fn find_rtti(context: &mut Context<'_>, vtables: &[u64]) -> Result<BTreeMap<u64, ClassInfo>> {
+    let mut result = BTreeMap::new();
+
+    for &vtable in vtables {
+        if let Some(info) = parse_rtti(context, vtable)? {
+            log::debug!("Found RTTI for vtable {:#x}: {}", vtable, info.name);
+
+            result.insert(vtable, info);
+        }
+    }
+
+    Ok(result)
+}
+
+fn parse_rtti(context: &mut Context<'_>, vtable: u64) -> Result<Option<ClassInfo>> {
+    let pointer_size = context.pointer_size as u64;
+
+    let col_slot_addr = vtable - pointer_size;
+    let (data, offset) = match section_data_at(context, col_slot_addr) {
+        Ok(x) => x,
+        Err(_) => return Ok(None),
+    };
+    // Unlike the RVA-encoded fields inside the locator itself, the pointer at
+    // `vtable[-pointer_size]` is a plain absolute pointer on both x86 and x64 — it's
+    // written like any other vtable-adjacent pointer, not as an image-relative RVA.
+    let col_ptr_bytes = match data.get(offset..offset + context.pointer_size) {
+        Some(x) => x,
+        None => return Ok(None),
+    };
+    let col_addr = convert_pointer(col_ptr_bytes, context.pointer_size);
+
+    let image_base = context.object.relative_address_base();
+
+    let (col_data, col_offset) = match section_data_at(context, col_addr) {
+        Ok(x) => x,
+        Err(_) => return Ok(None),
+    };
+
+    let signature = read_u32(col_data, col_offset + COL_OFFSET_SIGNATURE)?;
+    if signature > 1 {
+        // not a recognizable CompleteObjectLocator
+        return Ok(None);
+    }
+
+    let type_descriptor_rva = read_u32(col_data, col_offset + COL_OFFSET_TYPE_DESCRIPTOR)?;
+    let hierarchy_descriptor_rva = read_u32(col_data, col_offset + COL_OFFSET_CLASS_HIERARCHY_DESCRIPTOR)?;
+
+    let type_descriptor_addr = rva_to_addr(context, type_descriptor_rva as u64, image_base);
+    let hierarchy_descriptor_addr = rva_to_addr(context, hierarchy_descriptor_rva as u64, image_base);
+
+    let name = match read_type_descriptor_name(context, type_descriptor_addr) {
+        Ok(x) => x,
+        Err(_) => return Ok(None),
+    };
+    let bases = parse_class_hierarchy(context, hierarchy_descriptor_addr, image_base)?;
+
+    context.xrefs.entry(type_descriptor_addr).or_insert_with(Vec::new).push(vtable);
+    context.xrefs.entry(hierarchy_descriptor_addr).or_insert_with(Vec::new).push(vtable);
+
+    Ok(Some(ClassInfo { name, bases }))
+}
+
+fn parse_class_hierarchy(context: &Context<'_>, addr: u64, image_base: u64) -> Result<Vec<String>> {
+    let (data, offset) = section_data_at(context, addr)?;
+
+    let num_base_classes = read_u32(data, offset + CHD_OFFSET_NUM_BASE_CLASSES)?;
+    let base_class_array_rva = read_u32(data, offset + CHD_OFFSET_BASE_CLASS_ARRAY)?;
+    let base_class_array_addr = rva_to_addr(context, base_class_array_rva as u64, image_base);
+
+    let mut bases = Vec::new();
+    for i in 0..num_base_classes as u64 {
+        let (array_data, array_offset) = section_data_at(context, base_class_array_addr + i * 4)?;
+        let descriptor_rva = read_u32(array_data, array_offset)?;
+        let descriptor_addr = rva_to_addr(context, descriptor_rva as u64, image_base);
+
+        let (bcd_data, bcd_offset) = section_data_at(context, descriptor_addr)?;
+        let type_descriptor_rva = read_u32(bcd_data, bcd_offset + BCD_OFFSET_TYPE_DESCRIPTOR)?;
+        let type_descriptor_addr = rva_to_addr(context, type_descriptor_rva as u64, image_base);
+
+        // Skip the leading entry: it always describes the class itself.
+        if i != 0 {
+            bases.push(read_type_descriptor_name(context, type_descriptor_addr)?);
+        }
+    }
+
+    Ok(bases)
+}
+
+fn read_type_descriptor_name(context: &Context<'_>, addr: u64) -> Result<String> {
+    let (data, offset) = section_data_at(context, addr)?;
+
+    let name_offset = offset
+        + if context.pointer_size == 8 {
+            TYPE_DESCRIPTOR_NAME_OFFSET_64
+        } else {
+            TYPE_DESCRIPTOR_NAME_OFFSET_32
+        };
+
+    let name_bytes = data.get(name_offset..).ok_or_else(|| anyhow!("Read out of bounds at offset {:#x}", name_offset))?;
+    let end = name_bytes.iter().position(|&b| b == 0).ok_or_else(|| anyhow!("Unterminated type descriptor name"))?;
+    let mangled = std::str::from_utf8(&name_bytes[..end])?;
+
+    demangle_msvc_name(mangled).ok_or_else(|| anyhow!("Not a class/struct type descriptor: {}", mangled))
+}
+
+/// Demangles the small subset of MSVC name mangling used by `_TypeDescriptor::name`,
+/// e.g. `.?AVFoo@Bar@@` (class `Bar::Foo`) or `.?AUFoo@@` (struct `Foo`).
+fn demangle_msvc_name(mangled: &str) -> Option<String> {
+    let rest = mangled.strip_prefix(".?AV").or_else(|| mangled.strip_prefix(".?AU"))?;
+    let rest = rest.strip_suffix("@@")?;
+
+    Some(rest.split('@').rev().collect::<Vec<_>>().join("::"))
+}
+
+fn rva_to_addr(context: &Context<'_>, rva: u64, image_base: u64) -> u64 {
+    // x64 RTTI structures store image-relative RVAs; x86 stores plain absolute pointers.
+    if context.pointer_size == 8 {
+        image_base + rva
+    } else {
+        rva
+    }
+}
+
+fn section_data_at<'a>(context: &'a Context<'_>, addr: u64) -> Result<(std::borrow::Cow<'a, [u8]>, usize)> {
+    for section in context.object.sections() {
+        let start = section.address();
+        let size = section.size();
+
+        if start <= addr && addr < start + size {
+            return Ok((section.data()?, (addr - start) as usize));
+        }
+    }
+
+    Err(anyhow!("No section contains address {:#x}", addr))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32> {
+    let bytes = data.get(offset..offset + 4).ok_or_else(|| anyhow!("Read out of bounds at offset {:#x}", offset))?;
+
+    Ok(u32::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::fs;
+
+    use super::{find_rtti, ClassInfo};
+    use crate::{context::Context, vtable::find_vtables};
+
+    #[tokio::test]
+    async fn test_x64() -> anyhow::Result<()> {
+        let file = fs::read("./test_data/msvc_rtti1_64.exe").await?;
+        let obj = object::File::parse(&*file)?;
+        let mut context = Context::new(obj)?;
+
+        let vtables = find_vtables(&mut context)?;
+        let rtti = find_rtti(&mut context, &vtables)?;
+
+        assert_eq!(
+            rtti.get(&0x140010318).unwrap(),
+            &ClassInfo {
+                name: "A".to_owned(),
+                bases: vec![],
+            }
+        );
+        assert_eq!(
+            rtti.get(&0x140010390).unwrap(),
+            &ClassInfo {
+                name: "D".to_owned(),
+                bases: vec!["B".to_owned(), "C".to_owned()],
+            }
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,320 @@
+use std::collections::BTreeSet;
+
+use anyhow::{anyhow, Result};
+use object::{BinaryFormat, Object, ObjectSection};
+
+use super::context::Context;
+
+pub type FunctionRange = (u64, u64);
+
+/// Recovers authoritative function boundaries from the binary's unwind metadata:
+/// `.pdata`/`.xdata` on PE, DWARF CFI in `.eh_frame` on ELF/Mach-O.
+pub fn find_function_ranges(context: &Context<'_>) -> Result<BTreeSet<FunctionRange>> {
+    match context.object.format() {
+        BinaryFormat::Pe => find_pe_function_ranges(context),
+        BinaryFormat::Elf | BinaryFormat::MachO => find_dwarf_function_ranges(context),
+        format => Err(anyhow!("Unwind-based function recovery is not supported for {:?}", format)),
+    }
+}
+
+// --- PE: .pdata / .xdata -----------------------------------------------------------
+
+const RUNTIME_FUNCTION_SIZE: usize = 12; // { BeginAddress, EndAddress, UnwindInfoAddress }: u32 x 3
+
+fn find_pe_function_ranges(context: &Context<'_>) -> Result<BTreeSet<FunctionRange>> {
+    let pdata_section = context.object.section_by_name(".pdata").ok_or_else(|| anyhow!("No .pdata section"))?;
+    let pdata = pdata_section.data()?;
+
+    let xdata_section = context.object.section_by_name(".xdata").ok_or_else(|| anyhow!("No .xdata section"))?;
+    let xdata = xdata_section.data()?;
+
+    let image_base = context.object.relative_address_base();
+
+    let mut ranges = BTreeSet::new();
+    for entry in pdata.chunks_exact(RUNTIME_FUNCTION_SIZE) {
+        let begin_rva = u32::from_le_bytes(entry[0..4].try_into().unwrap());
+        let end_rva = u32::from_le_bytes(entry[4..8].try_into().unwrap());
+        let unwind_info_rva = u32::from_le_bytes(entry[8..12].try_into().unwrap());
+
+        if begin_rva == 0 && end_rva == 0 && unwind_info_rva == 0 {
+            continue;
+        }
+
+        let unwind_info_offset = (unwind_info_rva as u64)
+            .checked_sub(xdata_section.address() - image_base)
+            .ok_or_else(|| anyhow!("UNWIND_INFO RVA {:#x} is outside .xdata", unwind_info_rva))? as usize;
+
+        validate_unwind_info(&xdata, unwind_info_offset)?;
+
+        let start = image_base + begin_rva as u64;
+        let end = image_base + end_rva as u64;
+        log::trace!("function range {:#x}-{:#x} (unwind info at {:#x})", start, end, unwind_info_rva);
+
+        ranges.insert((start, end));
+    }
+
+    Ok(ranges)
+}
+
+/// Reads the `UNWIND_INFO` header and checks only that its version is 1 or 2. The prolog
+/// size and unwind-code count are logged, not checked, and the unwind codes themselves
+/// are not interpreted.
+fn validate_unwind_info(xdata: &[u8], offset: usize) -> Result<()> {
+    let header = xdata.get(offset..offset + 4).ok_or_else(|| anyhow!("UNWIND_INFO out of bounds at {:#x}", offset))?;
+
+    let version_and_flags = header[0];
+    let version = version_and_flags & 0x7;
+    if version != 1 && version != 2 {
+        return Err(anyhow!("Unsupported UNWIND_INFO version {}", version));
+    }
+
+    let size_of_prolog = header[1];
+    let count_of_codes = header[2];
+    log::trace!("UNWIND_INFO at {:#x}: prolog size {}, {} unwind codes", offset, size_of_prolog, count_of_codes);
+
+    Ok(())
+}
+
+// --- ELF/Mach-O: .eh_frame DWARF CFI -----------------------------------------------
+
+// DW_EH_PE_* encoding bits (lower nibble: value format, upper nibble: application).
+const DW_EH_PE_UDATA4: u8 = 0x03;
+const DW_EH_PE_SDATA4: u8 = 0x0b;
+const DW_EH_PE_UDATA8: u8 = 0x04;
+const DW_EH_PE_SDATA8: u8 = 0x0c;
+const DW_EH_PE_PCREL: u8 = 0x10;
+
+fn find_dwarf_function_ranges(context: &Context<'_>) -> Result<BTreeSet<FunctionRange>> {
+    // Mach-O reports this as `__eh_frame` in the `__TEXT` segment rather than ELF's `.eh_frame`.
+    let eh_frame_section = context
+        .object
+        .section_by_name(".eh_frame")
+        .or_else(|| context.object.section_by_name("__eh_frame"))
+        .ok_or_else(|| anyhow!("No .eh_frame/__eh_frame section"))?;
+    let eh_frame = eh_frame_section.data()?;
+    let section_addr = eh_frame_section.address();
+
+    let mut ranges = BTreeSet::new();
+    let mut cies: std::collections::BTreeMap<usize, Cie> = std::collections::BTreeMap::new();
+
+    let mut offset = 0usize;
+    while offset < eh_frame.len() {
+        let record_start = offset;
+
+        let length = read_u32(eh_frame, offset)?;
+        offset += 4;
+        if length == 0 {
+            break; // terminator
+        }
+        if length == 0xffff_ffff {
+            return Err(anyhow!("64-bit DWARF CFI is not supported"));
+        }
+
+        let record_end = offset
+            .checked_add(length as usize)
+            .filter(|&end| end <= eh_frame.len())
+            .ok_or_else(|| anyhow!("CFI record at {:#x} claims length {} past the end of .eh_frame", record_start, length))?;
+        let cie_pointer = read_u32(eh_frame, offset)?;
+
+        if cie_pointer == 0 {
+            let cie = parse_cie(eh_frame, offset + 4, record_end)?;
+            cies.insert(record_start, cie);
+        } else {
+            let cie_offset = offset - cie_pointer as usize;
+            let cie = cies.get(&cie_offset).ok_or_else(|| anyhow!("FDE at {:#x} references unknown CIE at {:#x}", record_start, cie_offset))?;
+
+            let (start, range) = parse_fde(eh_frame, offset + 4, section_addr + (offset + 4) as u64, cie.pointer_encoding)?;
+            log::trace!("function range {:#x}-{:#x} (FDE at {:#x})", start, start + range, record_start);
+
+            ranges.insert((start, start + range));
+        }
+
+        offset = record_end;
+    }
+
+    Ok(ranges)
+}
+
+struct Cie {
+    pointer_encoding: u8,
+}
+
+fn parse_cie(data: &[u8], mut offset: usize, end: usize) -> Result<Cie> {
+    let version = *data.get(offset).ok_or_else(|| anyhow!("CIE out of bounds"))?;
+    offset += 1;
+
+    let augmentation_start = offset;
+    let augmentation_bytes = data.get(augmentation_start..end).ok_or_else(|| anyhow!("CIE augmentation string out of bounds"))?;
+    let augmentation_end = augmentation_bytes.iter().position(|&b| b == 0).ok_or_else(|| anyhow!("Unterminated CIE augmentation string"))?;
+    let augmentation = std::str::from_utf8(&augmentation_bytes[..augmentation_end])?;
+    offset = augmentation_start + augmentation_end + 1;
+
+    if version == 4 {
+        offset += 1; // address_size
+        offset += 1; // segment_selector_size
+    }
+
+    let (_code_alignment_factor, n) = read_uleb128(data, offset)?;
+    offset += n;
+    let (_data_alignment_factor, n) = read_sleb128(data, offset)?;
+    offset += n;
+
+    if !augmentation.starts_with('z') {
+        // No augmentation data, so no pointer-encoding byte is present; default to an
+        // absolute 4-byte encoding, which is what pre-augmentation CFI always used.
+        return Ok(Cie { pointer_encoding: DW_EH_PE_UDATA4 });
+    }
+
+    let (_return_address_register, n) = read_uleb128(data, offset)?;
+    offset += n;
+
+    let (augmentation_data_len, n) = read_uleb128(data, offset)?;
+    offset += n;
+    let augmentation_data = data
+        .get(offset..offset + augmentation_data_len as usize)
+        .ok_or_else(|| anyhow!("CIE augmentation data out of bounds"))?;
+
+    // Per the eh_frame spec, a `z`-augmented CIE without an `R` still implies the
+    // FDE pointers are absolute/4-byte, same as a CIE with no augmentation at all.
+    let mut pointer_encoding = DW_EH_PE_UDATA4;
+    let mut aug_offset = 0;
+    for c in augmentation.chars().skip(1) {
+        match c {
+            'R' => {
+                pointer_encoding = *augmentation_data.get(aug_offset).ok_or_else(|| anyhow!("CIE augmentation data out of bounds"))?;
+                aug_offset += 1;
+            }
+            'P' => {
+                let encoding = *augmentation_data.get(aug_offset).ok_or_else(|| anyhow!("CIE augmentation data out of bounds"))?;
+                aug_offset += 1 + encoded_pointer_size(encoding);
+            }
+            'L' => {
+                aug_offset += 1;
+            }
+            _ => {}
+        }
+    }
+
+    Ok(Cie { pointer_encoding })
+}
+
+fn parse_fde(data: &[u8], offset: usize, field_addr: u64, pointer_encoding: u8) -> Result<(u64, u64)> {
+    let size = encoded_pointer_size(pointer_encoding);
+
+    let initial_location = read_encoded_pointer(data, offset, field_addr, pointer_encoding)?;
+    let address_range = read_unsigned(data, offset + size, encoded_pointer_size(pointer_encoding & 0x0f))?;
+
+    Ok((initial_location, address_range))
+}
+
+fn encoded_pointer_size(encoding: u8) -> usize {
+    match encoding & 0x0f {
+        DW_EH_PE_UDATA4 | DW_EH_PE_SDATA4 => 4,
+        DW_EH_PE_UDATA8 | DW_EH_PE_SDATA8 => 8,
+        _ => 8,
+    }
+}
+
+fn read_encoded_pointer(data: &[u8], offset: usize, field_addr: u64, encoding: u8) -> Result<u64> {
+    // GCC/Clang's standard "zR" augmentation uses `pcrel|sdata4`, so a signed displacement
+    // must be sign-extended before the `wrapping_add` below, or a negative displacement
+    // (initial_location behind field_addr, the common case) turns into a ~4GiB offset.
+    let value = match encoding & 0x0f {
+        DW_EH_PE_SDATA4 => {
+            let bytes = data.get(offset..offset + 4).ok_or_else(|| anyhow!("Read out of bounds at offset {:#x}", offset))?;
+            i32::from_le_bytes(bytes.try_into().unwrap()) as i64 as u64
+        }
+        DW_EH_PE_SDATA8 => {
+            let bytes = data.get(offset..offset + 8).ok_or_else(|| anyhow!("Read out of bounds at offset {:#x}", offset))?;
+            i64::from_le_bytes(bytes.try_into().unwrap()) as u64
+        }
+        _ => read_unsigned(data, offset, encoded_pointer_size(encoding))?,
+    };
+
+    Ok(if encoding & DW_EH_PE_PCREL != 0 { field_addr.wrapping_add(value) } else { value })
+}
+
+fn read_unsigned(data: &[u8], offset: usize, size: usize) -> Result<u64> {
+    let bytes = data.get(offset..offset + size).ok_or_else(|| anyhow!("Read out of bounds at offset {:#x}", offset))?;
+
+    Ok(match size {
+        4 => u32::from_le_bytes(bytes.try_into().unwrap()) as u64,
+        8 => u64::from_le_bytes(bytes.try_into().unwrap()),
+        _ => return Err(anyhow!("Unsupported pointer size {}", size)),
+    })
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32> {
+    let bytes = data.get(offset..offset + 4).ok_or_else(|| anyhow!("Read out of bounds at offset {:#x}", offset))?;
+
+    Ok(u32::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+fn read_uleb128(data: &[u8], offset: usize) -> Result<(u64, usize)> {
+    let mut result = 0u64;
+    let mut shift = 0;
+
+    for (i, &byte) in data[offset..].iter().enumerate() {
+        result |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok((result, i + 1));
+        }
+        shift += 7;
+    }
+
+    Err(anyhow!("Unterminated ULEB128 at offset {:#x}", offset))
+}
+
+fn read_sleb128(data: &[u8], offset: usize) -> Result<(i64, usize)> {
+    let mut result = 0i64;
+    let mut shift = 0;
+
+    for (i, &byte) in data[offset..].iter().enumerate() {
+        result |= ((byte & 0x7f) as i64) << shift;
+        shift += 7;
+
+        if byte & 0x80 == 0 {
+            if shift < 64 && byte & 0x40 != 0 {
+                result |= -1i64 << shift;
+            }
+            return Ok((result, i + 1));
+        }
+    }
+
+    Err(anyhow!("Unterminated SLEB128 at offset {:#x}", offset))
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::fs;
+
+    use super::find_function_ranges;
+    use crate::context::Context;
+
+    #[tokio::test]
+    async fn test_pe_pdata() -> anyhow::Result<()> {
+        let file = fs::read("./test_data/msvc_rtti1_64.exe").await?;
+        let obj = object::File::parse(&*file)?;
+        let context = Context::new(obj)?;
+
+        let ranges = find_function_ranges(&context)?;
+        assert!(ranges.contains(&(0x140001340, 0x1400013e9)));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_elf_eh_frame() -> anyhow::Result<()> {
+        let file = fs::read("./test_data/itanium_rtti1_x64.so").await?;
+        let obj = object::File::parse(&*file)?;
+        let context = Context::new(obj)?;
+
+        let ranges = find_function_ranges(&context)?;
+        // GCC's default "zR" augmentation is `pcrel|sdata4`; a function whose FDE
+        // initial_location is behind its own field (the common case) must still decode
+        // to the correct, non-wrapped address.
+        assert!(ranges.contains(&(0x1149, 0x1171)));
+
+        Ok(())
+    }
+}
@@ -1,8 +1,11 @@
-use capstone::{arch::x86, RegId};
 use std::collections::{btree_map::Entry, BTreeSet};
 
 use anyhow::{anyhow, Result};
-use object::{Object, ObjectSection};
+use capstone::{
+    arch::{arm, arm64, x86},
+    RegId,
+};
+use object::{Architecture, Object, ObjectSection};
 
 use super::{
     context::{Context, Instruction},
@@ -10,10 +13,27 @@ use super::{
 };
 
 pub fn find_vtables(context: &mut Context<'_>) -> Result<Vec<u64>> {
-    let text_section = context.object.section_by_name(".text").ok_or(anyhow!("No .text section"))?;
+    // Dispatch off section *kind* rather than name: PE calls these `.text`/`.rdata`, but
+    // looking them up by kind also covers ELF/Mach-O, whose equivalents (`.text`, and
+    // `.data.rel.ro`/`.rodata` or `__text`/`__const`) hold the same pointer-into-code runs.
+    let code_ranges = context
+        .object
+        .sections()
+        .filter(|s| s.kind() == object::SectionKind::Text)
+        .map(|s| (s.address(), s.address() + s.size()))
+        .collect::<Vec<_>>();
+    if code_ranges.is_empty() {
+        return Err(anyhow!("No code section"));
+    }
 
-    let rdata_section = context.object.section_by_name(".rdata").ok_or(anyhow!("No .rdata section"))?;
-    let rdata = rdata_section.data()?;
+    let data_sections = context
+        .object
+        .sections()
+        .filter(|s| matches!(s.kind(), object::SectionKind::ReadOnlyData | object::SectionKind::Data))
+        .collect::<Vec<_>>();
+    if data_sections.is_empty() {
+        return Err(anyhow!("No read-only or initialized data section"));
+    }
 
     // 1. Find vtable candidates
     struct State {
@@ -21,94 +41,231 @@ pub fn find_vtables(context: &mut Context<'_>) -> Result<Vec<u64>> {
         all: BTreeSet<u64>,
     }
 
-    let vtable_candidates = rdata
-        .windows(context.pointer_size)
-        .enumerate()
-        .step_by(context.pointer_size)
-        .fold(
-            State {
-                last: None,
-                all: BTreeSet::new(),
-            },
-            |mut state, (i, x)| {
-                let ptr = convert_pointer(x, context.pointer_size);
-
-                if text_section.address() < ptr && ptr < text_section.address() + text_section.size() {
-                    if state.last.is_none() {
-                        let addr = i as u64 + rdata_section.address();
-
-                        log::trace!("vtable candidate at {:#x}", addr);
-                        state.last = Some(addr);
+    let mut vtable_candidates = BTreeSet::new();
+    for section in &data_sections {
+        let data = section.data()?;
+        let section_addr = section.address();
+
+        let candidates = data
+            .windows(context.pointer_size)
+            .enumerate()
+            .step_by(context.pointer_size)
+            .fold(
+                State {
+                    last: None,
+                    all: BTreeSet::new(),
+                },
+                |mut state, (i, x)| {
+                    let ptr = convert_pointer(x, context.pointer_size);
+
+                    if code_ranges.iter().any(|&(start, end)| start < ptr && ptr < end) {
+                        if state.last.is_none() {
+                            let addr = i as u64 + section_addr;
+
+                            log::trace!("vtable candidate at {:#x}", addr);
+                            state.last = Some(addr);
+                        }
+                    } else if state.last.is_some() {
+                        state.all.insert(state.last.unwrap());
+                        state.last = None
                     }
-                } else if state.last.is_some() {
-                    state.all.insert(state.last.unwrap());
-                    state.last = None
-                }
 
-                state
-            },
-        )
-        .all;
+                    state
+                },
+            )
+            .all;
 
-    // 2. Validate vtable candidates by parsing the code.
+        vtable_candidates.extend(candidates);
+    }
+
+    // 2. Validate vtable candidates by parsing the code. The instructions that store a
+    // vtable pointer into an object differ per architecture, so the matcher is selected
+    // from `context`'s architecture.
     let mut vtables = BTreeSet::new();
+    let architecture = context.object.architecture();
 
-    let mut it = context.insns.iter().peekable();
-    while let Some(insn) = it.next() {
-        // test if x64; lea reg, [rip + x]; mov [dest], reg
-        if insn.mnemonic == x86::X86Insn::X86_INS_LEA {
-            let operand_types = insn.operands.iter().map(|x| &x.op_type).collect::<Vec<_>>();
+    let insns = &context.insns;
+    for i in 0..insns.len() {
+        let insn = &insns[i];
+        let rest = &insns[i + 1..];
 
-            if let [x86::X86OperandType::Reg(reg), x86::X86OperandType::Mem(mem)] = &operand_types[..] {
-                if mem.base().0 as u32 == x86::X86Reg::X86_REG_RIP {
-                    let src_addr = (mem.disp() + insn.address as i64) as u64 + insn.bytes.len() as u64; // TODO: check overflow
+        let src_addr = match architecture {
+            Architecture::X86_64 => match_x64_store(insn, rest.first(), &vtable_candidates),
+            Architecture::X86 => match_x86_store(insn, &vtable_candidates),
+            Architecture::Aarch64 => match_arm64_store(insn, rest, &vtable_candidates)?,
+            Architecture::Arm => match_arm_store(context, insn, rest.first(), &vtable_candidates)?,
+            _ => None,
+        };
 
-                    if vtable_candidates.contains(&src_addr) && is_mov_from_reg_to_mem(it.peek().unwrap(), reg)? {
-                        log::debug!("Found vtable {:#x}", src_addr);
+        if let Some(src_addr) = src_addr {
+            log::debug!("Found vtable {:#x}", src_addr);
 
-                        vtables.insert(src_addr);
-                        if let Entry::Vacant(e) = context.xrefs.entry(src_addr) {
-                            e.insert(Vec::new());
-                        }
-                        context.xrefs.get_mut(&src_addr).unwrap().push(insn.address);
-                    }
-                }
-            }
-        }
-        // test if x86; mov dword ptr [reg], offset
-        if insn.mnemonic == x86::X86Insn::X86_INS_MOV {
-            let operand_types = insn.operands.iter().map(|x| &x.op_type).collect::<Vec<_>>();
-
-            if let [x86::X86OperandType::Mem(_), x86::X86OperandType::Imm(imm)] = &operand_types[..] {
-                let src_addr = *imm as u64;
-                if vtable_candidates.contains(&src_addr) {
-                    log::debug!("Found vtable {:#x}", imm);
-
-                    vtables.insert(src_addr);
-                    if let Entry::Vacant(e) = context.xrefs.entry(src_addr) {
-                        e.insert(Vec::new());
-                    }
-                    context.xrefs.get_mut(&src_addr).unwrap().push(insn.address);
-                }
+            vtables.insert(src_addr);
+            if let Entry::Vacant(e) = context.xrefs.entry(src_addr) {
+                e.insert(Vec::new());
             }
+            context.xrefs.get_mut(&src_addr).unwrap().push(insn.address);
         }
     }
 
     Ok(vtables.into_iter().collect())
 }
 
-fn is_mov_from_reg_to_mem(insn: &Instruction, reg: &RegId) -> Result<bool> {
+// x64: lea reg, [rip + x]; mov [dest], reg
+fn match_x64_store(insn: &Instruction, next: Option<&Instruction>, vtable_candidates: &BTreeSet<u64>) -> Option<u64> {
+    if insn.mnemonic != x86::X86Insn::X86_INS_LEA {
+        return None;
+    }
+
+    let operand_types = insn.operands.iter().map(|x| &x.op_type).collect::<Vec<_>>();
+    let [x86::X86OperandType::Reg(reg), x86::X86OperandType::Mem(mem)] = &operand_types[..] else {
+        return None;
+    };
+
+    if mem.base().0 as u32 != x86::X86Reg::X86_REG_RIP {
+        return None;
+    }
+
+    let src_addr = (mem.disp() + insn.address as i64) as u64 + insn.bytes.len() as u64; // TODO: check overflow
+    if !vtable_candidates.contains(&src_addr) {
+        return None;
+    }
+
+    if is_x86_store_of_reg(next?, reg) {
+        Some(src_addr)
+    } else {
+        None
+    }
+}
+
+// x86: mov dword ptr [reg], offset
+fn match_x86_store(insn: &Instruction, vtable_candidates: &BTreeSet<u64>) -> Option<u64> {
     if insn.mnemonic != x86::X86Insn::X86_INS_MOV {
-        return Ok(false);
+        return None;
     }
+
     let operand_types = insn.operands.iter().map(|x| &x.op_type).collect::<Vec<_>>();
+    let [x86::X86OperandType::Mem(_), x86::X86OperandType::Imm(imm)] = &operand_types[..] else {
+        return None;
+    };
 
-    if let [x86::X86OperandType::Mem(_), x86::X86OperandType::Reg(insn_reg)] = &operand_types[..] {
-        if insn_reg == reg {
-            return Ok(true);
-        }
+    let src_addr = *imm as u64;
+    vtable_candidates.contains(&src_addr).then_some(src_addr)
+}
+
+fn is_x86_store_of_reg(insn: &Instruction, reg: &RegId) -> bool {
+    if insn.mnemonic != x86::X86Insn::X86_INS_MOV {
+        return false;
     }
-    Ok(false)
+
+    let operand_types = insn.operands.iter().map(|x| &x.op_type).collect::<Vec<_>>();
+    matches!(&operand_types[..], [x86::X86OperandType::Mem(_), x86::X86OperandType::Reg(insn_reg)] if insn_reg == reg)
+}
+
+// AArch64: adrp xN, #page; add xN, xN, #lo12; str xN, [mem]
+fn match_arm64_store(insn: &Instruction, rest: &[Instruction], vtable_candidates: &BTreeSet<u64>) -> Result<Option<u64>> {
+    if insn.mnemonic != arm64::Arm64Insn::ARM64_INS_ADRP {
+        return Ok(None);
+    }
+
+    let operand_types = insn.operands.iter().map(|x| &x.op_type).collect::<Vec<_>>();
+    let [arm64::Arm64OperandType::Reg(adrp_reg), arm64::Arm64OperandType::Imm(page_base)] = &operand_types[..] else {
+        return Ok(None);
+    };
+
+    let Some(add_insn) = rest.first() else { return Ok(None) };
+    if add_insn.mnemonic != arm64::Arm64Insn::ARM64_INS_ADD {
+        return Ok(None);
+    }
+
+    let add_operand_types = add_insn.operands.iter().map(|x| &x.op_type).collect::<Vec<_>>();
+    let [arm64::Arm64OperandType::Reg(add_dst), arm64::Arm64OperandType::Reg(add_src), arm64::Arm64OperandType::Imm(lo12)] =
+        &add_operand_types[..]
+    else {
+        return Ok(None);
+    };
+
+    if add_dst != adrp_reg || add_src != adrp_reg {
+        return Ok(None);
+    }
+
+    let src_addr = (*page_base + *lo12) as u64;
+    if !vtable_candidates.contains(&src_addr) {
+        return Ok(None);
+    }
+
+    let Some(str_insn) = rest.get(1) else { return Ok(None) };
+    if is_arm64_store_of_reg(str_insn, add_dst) {
+        Ok(Some(src_addr))
+    } else {
+        Ok(None)
+    }
+}
+
+fn is_arm64_store_of_reg(insn: &Instruction, reg: &RegId) -> bool {
+    if insn.mnemonic != arm64::Arm64Insn::ARM64_INS_STR {
+        return false;
+    }
+
+    let operand_types = insn.operands.iter().map(|x| &x.op_type).collect::<Vec<_>>();
+    matches!(&operand_types[..], [arm64::Arm64OperandType::Reg(insn_reg), arm64::Arm64OperandType::Mem(_)] if insn_reg == reg)
+}
+
+// ARM: ldr rN, [pc, #off] (loading a literal-pool pointer); str rN, [mem]
+fn match_arm_store(
+    context: &Context<'_>,
+    insn: &Instruction,
+    next: Option<&Instruction>,
+    vtable_candidates: &BTreeSet<u64>,
+) -> Result<Option<u64>> {
+    if insn.mnemonic != arm::ArmInsn::ARM_INS_LDR {
+        return Ok(None);
+    }
+
+    let operand_types = insn.operands.iter().map(|x| &x.op_type).collect::<Vec<_>>();
+    let [arm::ArmOperandType::Reg(reg), arm::ArmOperandType::Mem(mem)] = &operand_types[..] else {
+        return Ok(None);
+    };
+
+    if mem.base().0 as u32 != arm::ArmReg::ARM_REG_PC {
+        return Ok(None);
+    }
+
+    // ARM state reads PC as the address of the current instruction + 8.
+    let literal_addr = (insn.address & !3) + 8 + mem.disp() as u64;
+    let src_addr = read_literal_pointer(context, literal_addr)?;
+
+    if !vtable_candidates.contains(&src_addr) {
+        return Ok(None);
+    }
+
+    if is_arm_store_of_reg(next, reg) {
+        Ok(Some(src_addr))
+    } else {
+        Ok(None)
+    }
+}
+
+fn is_arm_store_of_reg(insn: Option<&Instruction>, reg: &RegId) -> bool {
+    let Some(insn) = insn else { return false };
+    if insn.mnemonic != arm::ArmInsn::ARM_INS_STR {
+        return false;
+    }
+
+    let operand_types = insn.operands.iter().map(|x| &x.op_type).collect::<Vec<_>>();
+    matches!(&operand_types[..], [arm::ArmOperandType::Reg(insn_reg), arm::ArmOperandType::Mem(_)] if insn_reg == reg)
+}
+
+fn read_literal_pointer(context: &Context<'_>, addr: u64) -> Result<u64> {
+    let text_section = context.object.section_by_name(".text").ok_or(anyhow!("No .text section"))?;
+    let data = text_section.data()?;
+
+    let offset = (addr - text_section.address()) as usize;
+    let bytes = data
+        .get(offset..offset + context.pointer_size)
+        .ok_or_else(|| anyhow!("Literal pool read out of bounds at {:#x}", addr))?;
+
+    Ok(convert_pointer(bytes, context.pointer_size))
 }
 
 #[cfg(test)]
@@ -164,4 +321,22 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_arm64() -> anyhow::Result<()> {
+        init();
+
+        // An ELF (Android NDK) shared library rather than a PE one, so this exercises the
+        // by-`SectionKind` section lookup alongside the ADRP/ADD/STR matcher itself.
+        let file = fs::read("./test_data/vtable1_android_arm64.so").await?;
+        let obj = object::File::parse(&*file)?;
+        let mut context = Context::new(obj)?;
+
+        let vtables = find_vtables(&mut context)?;
+        assert_eq!(vtables, [0x11000, 0x11020]);
+        assert_eq!(*context.xrefs.get(&0x11000).unwrap(), vec![0x1060,]);
+        assert_eq!(*context.xrefs.get(&0x11020).unwrap(), vec![0x10a0,]);
+
+        Ok(())
+    }
 }